@@ -1,8 +1,11 @@
 """
 """
+import math
+import statistics
 import typing
 
 CASH = '$'
+TRADING_DAYS = 252
 
 class Account:
 
@@ -29,4 +32,210 @@ class Account:
         for symbol, lots in tax_lots.items():
             holdings[symbol] = sum(lot['shares'] for lot in lots)
 
-        return cls(holdings, tax_lots=tax_lots)
\ No newline at end of file
+        return cls(holdings, tax_lots=tax_lots)
+
+
+def _weight_vector(weights: dict, tickers: list) -> list:
+    # a weight outside tickers has no covariance row and can't be dropped quietly
+    assert set(weights) <= set(tickers), 'weights must only contain tickers covered by the covariance'
+    return [weights.get(ticker, 0.0) for ticker in tickers]
+
+def _quadratic_form(vector: list, covariance: list) -> float:
+    assert len(covariance) == len(vector), 'covariance must be n x n for n tickers'
+    for row in covariance:
+        assert len(row) == len(vector), 'covariance must be n x n for n tickers'
+
+    return sum(
+        vector[i] * covariance[i][j] * vector[j]
+        for i in range(len(vector))
+        for j in range(len(vector))
+    )
+
+def compute_tracking_error(weights: dict, benchmark_weights: dict, covariance: list, tickers: list, annualize: bool = False) -> float:
+    # ex-ante tracking error: sqrt((w - w_b)^T Sigma (w - w_b)), covariance is daily
+    active = [
+        w - b for w, b in zip(_weight_vector(weights, tickers), _weight_vector(benchmark_weights, tickers))
+    ]
+    variance = _quadratic_form(active, covariance)
+    # a singular covariance can give a tiny negative variance from roundoff
+    assert variance >= -1e-12, 'covariance must be positive semi-definite'
+
+    tracking_error = math.sqrt(max(variance, 0.0))
+    if annualize:
+        tracking_error *= math.sqrt(TRADING_DAYS)
+    return tracking_error
+
+def compute_dollar_neutral_weights(signal: list, top_n: int, bottom_n: int) -> dict:
+    # long the top_n and short the bottom_n names, each side summing to 0.5 in absolute value
+    assert top_n > 0 and bottom_n > 0, 'top_n and bottom_n must be positive'
+    assert top_n + bottom_n <= len(signal), 'not enough tickers for top_n + bottom_n'
+
+    ranked = sorted(signal, key=lambda pair: pair[1], reverse=True)
+    weights = {}
+    for ticker, _ in ranked[:top_n]:
+        weights[ticker] = 0.5 / top_n
+    for ticker, _ in ranked[len(ranked) - bottom_n:]:
+        weights[ticker] = -0.5 / bottom_n
+    return weights
+
+def herfindahl_index(weights: dict) -> float:
+    return sum(w ** 2 for w in weights.values())
+
+def effective_n(weights: dict) -> float:
+    hhi = herfindahl_index(weights)
+    return 1 / hhi if hhi else 0.0
+
+def compute_diversification_ratio(weights: dict, covariance: list, tickers: list) -> float:
+    # (sum(|w_i| * sigma_i)) / sqrt(w^T Sigma w); gross weights keep long/short ratios >= 1
+    vector = _weight_vector(weights, tickers)
+    variance = _quadratic_form(vector, covariance)
+    assert variance > 0, 'portfolio variance must be positive'
+
+    weighted_vol = sum(abs(w) * math.sqrt(covariance[i][i]) for i, w in enumerate(vector))
+    return weighted_vol / math.sqrt(variance)
+
+def compute_rolling_ir(alpha_returns: list, benchmark_returns: list, window: int) -> list:
+    # annualized mean active return / annualized tracking error over each window
+    assert window > 1, 'window must be at least 2'
+
+    benchmark = dict(benchmark_returns)
+    active = [(date, r - benchmark[date]) for date, r in alpha_returns if date in benchmark]
+
+    series = []
+    for end in range(window, len(active) + 1):
+        values = [r for _, r in active[end - window:end]]
+        std = statistics.stdev(values)
+        if std == 0:
+            continue
+        series.append((active[end - 1][0], statistics.mean(values) / std * math.sqrt(TRADING_DAYS)))
+    return series
+
+def compute_portfolio_turnover_from_weights(old_weights: dict, new_weights: dict) -> float:
+    tickers = set(old_weights) | set(new_weights)
+    return sum(abs(new_weights.get(t, 0.0) - old_weights.get(t, 0.0)) for t in tickers) / 2
+
+def estimate_rebalance_cost(old_weights: dict, new_weights: dict, nav: float, fee_bps: float) -> float:
+    turnover = compute_portfolio_turnover_from_weights(old_weights, new_weights)
+    return turnover * 2 * nav * fee_bps / 10_000
+
+def net_exposure(weights: dict) -> float:
+    return sum(weights.values())
+
+def compute_net_exposure_series(holdings_history: list) -> list:
+    return [(date, net_exposure(weights)) for date, weights in holdings_history]
+
+def _zscore(signal: dict) -> dict:
+    # cross-sectional z-score; a flat signal scores zero everywhere
+    if not signal:
+        return {}
+    mean = statistics.mean(signal.values())
+    std = statistics.pstdev(signal.values())
+    return {ticker: (value - mean) / std if std else 0.0 for ticker, value in signal.items()}
+
+def compute_composite_factor_score(signals: list, exclude_missing: bool = True) -> dict:
+    # signals are (factor_name, signal, weight); missing values are imputed as a zero z-score
+    # unless exclude_missing drops tickers that aren't in every signal
+    if not signals:
+        return {}
+    ticker_sets = [set(signal) for _, signal, _ in signals]
+    if exclude_missing:
+        # z-score over the kept universe only, so dropped tickers don't shift the mean or std
+        tickers = set.intersection(*ticker_sets)
+        signals = [
+            (name, {t: v for t, v in signal.items() if t in tickers}, weight)
+            for name, signal, weight in signals
+        ]
+    else:
+        tickers = set.union(*ticker_sets)
+    zscores = [(_zscore(signal), weight) for _, signal, weight in signals]
+
+    return {
+        ticker: sum(weight * zscore.get(ticker, 0.0) for zscore, weight in zscores)
+        for ticker in tickers
+    }
+
+def expected_return_ex_ante(return_forecasts: dict, target_weights: dict) -> float:
+    # tickers without a forecast contribute nothing
+    return sum(w * return_forecasts.get(ticker, 0.0) for ticker, w in target_weights.items())
+
+def compute_quality_score(roe: dict, debt_to_equity: dict, earnings_stability: dict, weights: tuple, fill_missing_with_zero: bool = False) -> dict:
+    # low leverage is high quality, so debt_to_equity scores with the sign flipped
+    roe_weight, leverage_weight, stability_weight = weights
+    return compute_composite_factor_score(
+        [
+            ('roe', roe, roe_weight),
+            ('debt_to_equity', {t: -v for t, v in debt_to_equity.items()}, leverage_weight),
+            ('earnings_stability', earnings_stability, stability_weight),
+        ],
+        exclude_missing=not fill_missing_with_zero,
+    )
+
+def compute_growth_signal(current: dict, prior: dict) -> dict:
+    # dividing by |prior| keeps improvement positive when prior is negative;
+    # tickers with no prior (or a zero prior) have no defined growth rate
+    return {
+        ticker: (value - prior[ticker]) / abs(prior[ticker])
+        for ticker, value in current.items()
+        if prior.get(ticker)
+    }
+
+def compute_growth_zscore(current: dict, prior: dict) -> dict:
+    return _zscore(compute_growth_signal(current, prior))
+
+def _invert(matrix: list) -> list:
+    # gauss-jordan elimination with partial pivoting
+    n = len(matrix)
+    augmented = [list(row) + [float(i == j) for j in range(n)] for i, row in enumerate(matrix)]
+    for col in range(n):
+        pivot = max(range(col, n), key=lambda r: abs(augmented[r][col]))
+        assert abs(augmented[pivot][col]) > 1e-12, 'matrix is singular'
+        augmented[col], augmented[pivot] = augmented[pivot], augmented[col]
+
+        scale = augmented[col][col]
+        augmented[col] = [v / scale for v in augmented[col]]
+        for r in range(n):
+            if r != col:
+                factor = augmented[r][col]
+                augmented[r] = [v - factor * p for v, p in zip(augmented[r], augmented[col])]
+    return [row[n:] for row in augmented]
+
+def _mat_vec(matrix: list, vector: list) -> list:
+    return [sum(m * v for m, v in zip(row, vector)) for row in matrix]
+
+def compute_black_litterman_weights(tickers: list, benchmark_weights: dict, views: list, tau: float, risk_aversion: float, covariance: list) -> dict:
+    # views are (pick_vector, return_view, view_uncertainty); with no views the
+    # posterior is the equilibrium and the benchmark weights come back unchanged
+    n = len(tickers)
+    assert tau > 0, 'tau must be positive'
+    assert risk_aversion > 0, 'risk_aversion must be positive'
+    assert len(covariance) == n and all(len(row) == n for row in covariance), \
+        'covariance must be n x n for n tickers'
+
+    # implied equilibrium returns: pi = delta * Sigma * w_b
+    equilibrium = [risk_aversion * r for r in _mat_vec(covariance, _weight_vector(benchmark_weights, tickers))]
+
+    # posterior: [(tau Sigma)^-1 + P^T Omega^-1 P]^-1 [(tau Sigma)^-1 pi + P^T Omega^-1 Q]
+    precision = _invert([[tau * c for c in row] for row in covariance])
+    lhs = [list(row) for row in precision]
+    rhs = _mat_vec(precision, equilibrium)
+    for pick, view, uncertainty in views:
+        assert len(pick) == n, 'pick vector must have one entry per ticker'
+        assert uncertainty > 0, 'view uncertainty must be positive'
+        for i in range(n):
+            rhs[i] += pick[i] * view / uncertainty
+            for j in range(n):
+                lhs[i][j] += pick[i] * pick[j] / uncertainty
+    posterior = _mat_vec(_invert(lhs), rhs)
+
+    weights = _mat_vec(_invert([[risk_aversion * c for c in row] for row in covariance]), posterior)
+    return dict(zip(tickers, weights))
+
+def compute_turnover_cost_series(daily_weight_changes: list, nav_series: list, fee_bps: float) -> list:
+    navs = dict(nav_series)
+    for date, _ in daily_weight_changes:
+        assert date in navs, f'no nav for {date}'
+
+    return [
+        (date, sum(abs(dw) for dw in changes.values()) * navs[date] * fee_bps / 10_000)
+        for date, changes in daily_weight_changes
+    ]
@@ -1,3 +1,6 @@
+import math
+import statistics
+
 from dumbfi import dumbbt
 
 def test_from_holdings():
@@ -33,3 +36,252 @@ def test_from_tax_lots():
     except AssertionError:
         pass
 
+def test_compute_tracking_error():
+    tickers = ['AAPL', 'MSFT']
+    covariance = [[0.0004, 0.0001], [0.0001, 0.0009]]
+
+    # identical weights have no tracking error
+    weights = {'AAPL': 0.5, 'MSFT': 0.5}
+    assert dumbbt.compute_tracking_error(weights, weights, covariance, tickers) == 0
+
+    # active weights of (+0.1, -0.1): 0.01 * (0.0004 - 2 * 0.0001 + 0.0009)
+    benchmark = {'AAPL': 0.4, 'MSFT': 0.6}
+    te = dumbbt.compute_tracking_error(weights, benchmark, covariance, tickers)
+    assert math.isclose(te, math.sqrt(0.01 * 0.0011))
+
+    annualized = dumbbt.compute_tracking_error(weights, benchmark, covariance, tickers, annualize=True)
+    assert math.isclose(annualized, te * math.sqrt(252))
+
+    # covariance must match the number of tickers
+    try:
+        dumbbt.compute_tracking_error(weights, benchmark, [[0.0004]], tickers)
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+    # a rank-deficient covariance can round the variance just below zero
+    singular = [[0.04, 0.06], [0.06, 0.09]]
+    weights = {'AAPL': 0.33, 'MSFT': -2 * 0.33 / 3}
+    assert dumbbt.compute_tracking_error(weights, {}, singular, tickers) == 0
+
+    try:
+        dumbbt.compute_tracking_error({'AAPL': 1.0}, {}, [[-0.04, 0.0], [0.0, 0.04]], tickers)
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+    # every position must be covered by the covariance
+    try:
+        dumbbt.compute_tracking_error({'AAPL': 0.5, 'GOOG': 0.5}, {'AAPL': 1.0}, [[0.0004]], ['AAPL'])
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+def test_compute_dollar_neutral_weights():
+    signal = [('AAPL', 0.3), ('MSFT', -0.1), ('GOOG', 0.5), ('AMZN', -0.4), ('META', 0.0)]
+    weights = dumbbt.compute_dollar_neutral_weights(signal, 2, 1)
+    assert weights == {'GOOG': 0.25, 'AAPL': 0.25, 'AMZN': -0.5}
+    assert math.isclose(sum(weights.values()), 0, abs_tol=1e-12)
+
+    # can't long and short more names than there are
+    try:
+        dumbbt.compute_dollar_neutral_weights(signal, 3, 3)
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+def test_herfindahl_index():
+    weights = {'AAPL': 0.25, 'MSFT': 0.25, 'GOOG': 0.25, 'AMZN': 0.25}
+    assert dumbbt.herfindahl_index(weights) == 0.25
+    assert dumbbt.effective_n(weights) == 4
+
+    assert dumbbt.herfindahl_index({'AAPL': 1.0}) == 1
+    assert dumbbt.effective_n({'AAPL': 1.0}) == 1
+    assert dumbbt.effective_n({}) == 0
+
+def test_compute_diversification_ratio():
+    tickers = ['AAPL', 'MSFT']
+    covariance = [[0.04, 0.0], [0.0, 0.04]]
+
+    # a single asset has no diversification benefit
+    ratio = dumbbt.compute_diversification_ratio({'AAPL': 1.0}, covariance, tickers)
+    assert math.isclose(ratio, 1)
+
+    # two uncorrelated assets with equal vol
+    ratio = dumbbt.compute_diversification_ratio({'AAPL': 0.5, 'MSFT': 0.5}, covariance, tickers)
+    assert math.isclose(ratio, math.sqrt(2))
+
+    # shorts count at their gross weight
+    ratio = dumbbt.compute_diversification_ratio({'AAPL': 1.0, 'MSFT': -1.0}, covariance, tickers)
+    assert math.isclose(ratio, math.sqrt(2))
+    ratio = dumbbt.compute_diversification_ratio({'AAPL': 0.5, 'MSFT': -1.0}, covariance, tickers)
+    assert math.isclose(ratio, 1.5 / math.sqrt(1.25))
+
+    try:
+        dumbbt.compute_diversification_ratio({}, covariance, tickers)
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+    # every position must be covered by the covariance
+    try:
+        dumbbt.compute_diversification_ratio({'AAPL': 0.5, 'GOOG': 0.5}, [[0.04]], ['AAPL'])
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+def test_compute_rolling_ir():
+    alpha = [('2020-01-01', 0.02), ('2020-01-02', 0.01), ('2020-01-03', 0.03), ('2020-01-06', 0.00)]
+    benchmark = [('2020-01-01', 0.01), ('2020-01-02', 0.01), ('2020-01-03', 0.01), ('2020-01-06', 0.01)]
+    series = dumbbt.compute_rolling_ir(alpha, benchmark, 3)
+
+    # active returns are 0.01, 0.00, 0.02, -0.01
+    assert [date for date, _ in series] == ['2020-01-03', '2020-01-06']
+    assert math.isclose(series[0][1], 0.01 / 0.01 * math.sqrt(252))
+    assert math.isclose(series[1][1], (0.01 / 3) / statistics.stdev([0.0, 0.02, -0.01]) * math.sqrt(252))
+
+    # dates missing from the benchmark are dropped
+    assert dumbbt.compute_rolling_ir(alpha, benchmark[:2], 3) == []
+
+def test_compute_portfolio_turnover_from_weights():
+    old_weights = {'AAPL': 0.5, 'MSFT': 0.5}
+    new_weights = {'AAPL': 0.25, 'MSFT': 0.5, 'GOOG': 0.25}
+    assert dumbbt.compute_portfolio_turnover_from_weights(old_weights, new_weights) == 0.25
+    assert dumbbt.compute_portfolio_turnover_from_weights(old_weights, old_weights) == 0
+
+    # 0.25 turnover trades 50,000 of 100,000 nav at 10bps
+    cost = dumbbt.estimate_rebalance_cost(old_weights, new_weights, 100_000, 10)
+    assert math.isclose(cost, 50)
+
+def test_compute_net_exposure_series():
+    history = [
+        ('2020-01-01', {'AAPL': 0.6, 'MSFT': 0.4}),
+        ('2020-01-02', {'AAPL': 0.5, 'MSFT': 0.3}),
+        ('2020-01-03', {'AAPL': 0.5, 'MSFT': -0.8}),
+    ]
+    series = dumbbt.compute_net_exposure_series(history)
+    assert [date for date, _ in series] == ['2020-01-01', '2020-01-02', '2020-01-03']
+    assert [round(exposure, 10) for _, exposure in series] == [1.0, 0.8, -0.3]
+
+def test_compute_composite_factor_score():
+    value = {'AAPL': 1.0, 'MSFT': 2.0, 'GOOG': 3.0}
+    momentum = {'AAPL': 3.0, 'MSFT': 2.0}
+
+    # GOOG is excluded, so over AAPL / MSFT value z-scores are -1 / +1 and momentum +1 / -1
+    scores = dumbbt.compute_composite_factor_score([('value', value, 0.75), ('momentum', momentum, 0.25)])
+    assert set(scores) == {'AAPL', 'MSFT'}
+    assert math.isclose(scores['AAPL'], -0.5)
+    assert math.isclose(scores['MSFT'], 0.5)
+
+    # an excluded outlier doesn't move the kept z-scores
+    scores = dumbbt.compute_composite_factor_score(
+        [('value', {'AAPL': 1.0, 'MSFT': 2.0, 'GOOG': 100.0}, 1.0), ('momentum', {'AAPL': 1.0, 'MSFT': 2.0}, 1.0)]
+    )
+    assert math.isclose(scores['AAPL'], -2)
+    assert math.isclose(scores['MSFT'], 2)
+
+    scores = dumbbt.compute_composite_factor_score(
+        [('value', value, 0.5), ('momentum', momentum, 0.5)], exclude_missing=False
+    )
+    assert set(scores) == {'AAPL', 'MSFT', 'GOOG'}
+    assert math.isclose(scores['GOOG'], 0.5 * math.sqrt(1.5))
+
+    assert dumbbt.compute_composite_factor_score([]) == {}
+
+def test_expected_return_ex_ante():
+    forecasts = {'AAPL': 0.10, 'MSFT': 0.05}
+    weights = {'AAPL': 0.5, 'MSFT': 0.3, 'GOOG': 0.2}
+    assert math.isclose(dumbbt.expected_return_ex_ante(forecasts, weights), 0.065)
+    assert dumbbt.expected_return_ex_ante(forecasts, {}) == 0
+
+def test_compute_quality_score():
+    roe = {'AAPL': 0.3, 'MSFT': 0.1}
+    debt_to_equity = {'AAPL': 2.0, 'MSFT': 1.0}
+    stability = {'AAPL': 0.9, 'MSFT': 0.5, 'GOOG': 0.7}
+
+    # AAPL wins on roe and stability but loses on leverage; GOOG is excluded,
+    # so every component z-scores to +1 / -1 over AAPL / MSFT
+    scores = dumbbt.compute_quality_score(roe, debt_to_equity, stability, (0.5, 0.25, 0.25))
+    assert set(scores) == {'AAPL', 'MSFT'}
+    assert math.isclose(scores['AAPL'], 0.5 * 1 + 0.25 * -1 + 0.25 * 1)
+    assert math.isclose(scores['MSFT'], 0.5 * -1 + 0.25 * 1 + 0.25 * -1)
+
+    # an excluded ticker's values don't feed the kept z-scores
+    outlier = {'AAPL': 0.9, 'MSFT': 0.5, 'GOOG': -50.0}
+    assert dumbbt.compute_quality_score(roe, debt_to_equity, outlier, (0.5, 0.25, 0.25)) == scores
+
+    scores = dumbbt.compute_quality_score(roe, debt_to_equity, stability, (0.5, 0.25, 0.25), fill_missing_with_zero=True)
+    assert math.isclose(scores['GOOG'], 0)
+
+def test_compute_growth_signal():
+    current = {'AAPL': 1.2, 'MSFT': -1.0, 'GOOG': 2.0, 'AMZN': 1.0}
+    prior = {'AAPL': 1.0, 'MSFT': -2.0, 'GOOG': 0.0}
+    growth = dumbbt.compute_growth_signal(current, prior)
+    assert set(growth) == {'AAPL', 'MSFT'}
+    assert math.isclose(growth['AAPL'], 0.2)
+
+    # a smaller loss is positive growth
+    assert math.isclose(growth['MSFT'], 0.5)
+
+    zscores = dumbbt.compute_growth_zscore(current, prior)
+    assert math.isclose(zscores['AAPL'], -1)
+    assert math.isclose(zscores['MSFT'], 1)
+
+def test_compute_black_litterman_weights():
+    tickers = ['AAPL', 'MSFT']
+    covariance = [[0.04, 0.0], [0.0, 0.04]]
+    benchmark = {'AAPL': 0.6, 'MSFT': 0.4}
+
+    # no views returns the benchmark
+    weights = dumbbt.compute_black_litterman_weights(tickers, benchmark, [], 0.05, 2.5, covariance)
+    assert math.isclose(weights['AAPL'], 0.6)
+    assert math.isclose(weights['MSFT'], 0.4)
+
+    # equilibrium AAPL return is 0.06; a 0.10 view with the same confidence as the
+    # prior (tau * sigma^2 = 0.002) moves the posterior halfway to 0.08
+    views = [([1.0, 0.0], 0.10, 0.002)]
+    weights = dumbbt.compute_black_litterman_weights(tickers, benchmark, views, 0.05, 2.5, covariance)
+    assert math.isclose(weights['AAPL'], 0.8)
+    assert math.isclose(weights['MSFT'], 0.4)
+
+    try:
+        dumbbt.compute_black_litterman_weights(tickers, benchmark, [([1.0], 0.10, 0.002)], 0.05, 2.5, covariance)
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+    # the benchmark can't hold tickers the covariance doesn't cover
+    try:
+        dumbbt.compute_black_litterman_weights(['AAPL'], {'AAPL': 0.5, 'GOOG': 0.5}, [], 0.05, 2.5, [[0.04]])
+    except AssertionError:
+        pass
+    else:
+        assert False
+
+def test_compute_turnover_cost_series():
+    changes = [
+        ('2020-01-01', {'AAPL': 0.1, 'MSFT': -0.1}),
+        ('2020-01-02', {}),
+        ('2020-01-03', {'AAPL': -0.05}),
+    ]
+    navs = [('2020-01-01', 100_000), ('2020-01-02', 101_000), ('2020-01-03', 102_000)]
+    series = dumbbt.compute_turnover_cost_series(changes, navs, 10)
+    assert [date for date, _ in series] == ['2020-01-01', '2020-01-02', '2020-01-03']
+    assert math.isclose(series[0][1], 20)
+    assert series[1][1] == 0
+    assert math.isclose(series[2][1], 5.1)
+
+    # every date with weight changes needs a nav
+    try:
+        dumbbt.compute_turnover_cost_series(changes, navs[:2], 10)
+    except AssertionError:
+        pass
+    else:
+        assert False